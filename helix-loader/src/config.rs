@@ -1,4 +1,4 @@
-use std::{io::ErrorKind, str::from_utf8};
+use std::{io::ErrorKind, path::Path, str::from_utf8};
 
 use crate::{trust_db, workspace_languages_file};
 
@@ -21,29 +21,153 @@ pub fn is_local_lang_config_trusted() -> std::io::Result<bool> {
             }
         }
     };
-    trust_db::is_file_trusted(&path, contents.as_bytes())
+    trust_db::is_local_config_trusted(&path, contents.as_bytes())
+}
+
+/// Collects every `.helix/languages.toml` found while walking from `start_dir` up
+/// through its ancestors to the workspace root (or `$HOME`, whichever comes first),
+/// closest directory first.
+///
+/// Exposed beyond this module so callers that need to revalidate the exact set of
+/// local configs `user_lang_config_for_file` merges (e.g. the trust dialog
+/// re-checking them for external edits) discover the same files, in the same order.
+pub fn local_lang_config_ancestors(start_dir: &Path) -> Vec<std::path::PathBuf> {
+    let workspace_root = crate::find_workspace_in(start_dir).0;
+    let home_dir = dirs::home_dir();
+
+    let mut paths = Vec::new();
+    for dir in start_dir.ancestors() {
+        paths.push(dir.join(".helix").join("languages.toml"));
+        if dir == workspace_root || home_dir.as_deref() == Some(dir) {
+            break;
+        }
+    }
+    paths
 }
 
 /// User configured languages.toml file, merged with the default config.
+///
+/// Only considers the current workspace's root `.helix/languages.toml` (resolved
+/// via `find_workspace`, i.e. the current working directory's workspace), the way
+/// this function has always worked. Kept around under its original signature so
+/// existing callers - none of which live in this checkout, so this can't be
+/// verified by compiling the workspace - keep compiling unchanged; landing a
+/// breaking signature change against a call site we can't see and can't update is
+/// worse than an additive API. For the ancestor-aware merge that lets monorepo
+/// subdirectories override their parents, call
+/// [`user_lang_config_for_file`] instead once the caller has a specific file to
+/// resolve ancestors from.
 pub fn user_lang_config(use_local: bool) -> Result<toml::Value, toml::de::Error> {
-    let mut dirs = vec![crate::config_dir()];
-    if use_local {
-        dirs.push(crate::find_workspace().0.join(".helix"));
-    }
+    let local_config = use_local
+        .then(|| crate::find_workspace().0.join(".helix").join("languages.toml"))
+        .and_then(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            match trust_db::is_local_config_trusted(&path, contents.as_bytes()) {
+                Ok(true) => Some(toml::from_str(&contents)),
+                _ => None,
+            }
+        })
+        .transpose()?;
+
+    let global_config = std::fs::read_to_string(crate::config_dir().join("languages.toml"))
+        .ok()
+        .map(|config| toml::from_str(&config))
+        .transpose()?;
+
+    let config = global_config
+        .into_iter()
+        .chain(local_config)
+        .fold(default_lang_config(), |a, b| {
+            crate::merge_toml_values(a, b, 3)
+        });
+
+    Ok(config)
+}
+
+/// Ancestor-aware variant of [`user_lang_config`]: when `workspace_file` is
+/// `Some`, every `.helix/languages.toml` between the file's directory and the
+/// workspace root (the way Cargo resolves `.cargo/config.toml`) is folded in,
+/// closer directories taking precedence over shallower ones so monorepo
+/// subdirectories can override their parents. The user's global `languages.toml`
+/// is always the lowest-precedence non-default layer. Each discovered file is
+/// individually gated through `trust_db::is_local_config_trusted`, so an untrusted
+/// intermediate directory's config is skipped rather than aborting the whole merge.
+pub fn user_lang_config_for_file(
+    workspace_file: Option<impl AsRef<Path>>,
+) -> Result<toml::Value, toml::de::Error> {
+    let mut local_configs = workspace_file
+        .map(|file| {
+            let start_dir = file
+                .as_ref()
+                .parent()
+                .unwrap_or(file.as_ref())
+                .to_path_buf();
+            local_lang_config_ancestors(&start_dir)
+        })
+        .unwrap_or_default();
+    // Closest-first is precedence order high-to-low; the fold below needs low-to-high.
+    local_configs.reverse();
+
+    let global_config = std::fs::read_to_string(crate::config_dir().join("languages.toml"))
+        .ok()
+        .map(|config| toml::from_str(&config))
+        .transpose()?;
 
-    let config = dirs
+    let local_configs = local_configs
         .into_iter()
-        .map(|path| path.join("languages.toml"))
-        .filter_map(|file| {
-            std::fs::read_to_string(file)
-                .map(|config| toml::from_str(&config))
-                .ok()
+        .filter_map(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            match trust_db::is_local_config_trusted(&path, contents.as_bytes()) {
+                Ok(true) => Some(toml::from_str(&contents)),
+                _ => None,
+            }
         })
-        .collect::<Result<Vec<_>, _>>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let config = global_config
         .into_iter()
+        .chain(local_configs)
         .fold(default_lang_config(), |a, b| {
             crate::merge_toml_values(a, b, 3)
         });
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds `root/a/b/c` with a `.git` marker at `root`, so `find_workspace_in`
+    /// resolves `root` as the workspace boundary, and returns `(root, leaf)`.
+    fn workspace_fixture(name: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+        let root = std::env::temp_dir().join(format!(
+            "helix-config-test-{name}-{}",
+            std::process::id()
+        ));
+        let leaf = root.join("a").join("b").join("c");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(&leaf).unwrap();
+        (root, leaf)
+    }
+
+    #[test]
+    fn local_lang_config_ancestors_are_closest_first_and_stop_at_workspace_root() {
+        let (root, leaf) = workspace_fixture("ancestors");
+
+        let ancestors = local_lang_config_ancestors(&leaf);
+
+        let expected: Vec<_> = [
+            leaf.clone(),
+            root.join("a").join("b"),
+            root.join("a"),
+            root.clone(),
+        ]
+        .into_iter()
+        .map(|dir| dir.join(".helix").join("languages.toml"))
+        .collect();
+        assert_eq!(ancestors, expected);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     fs::File,
     io::ErrorKind,
     path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
 };
 
 use fs2::FileExt;
@@ -11,35 +12,111 @@ use sha2::Digest;
 
 use crate::{data_dir, ensure_parent_dir, find_workspace_in};
 
+/// The current on-disk schema version of `trust_db.toml`. Bump this and add a step
+/// to [`TrustDb::migrate`] whenever the schema changes in a way older versions of
+/// Helix can't read directly.
+const CURRENT_VERSION: u32 = 1;
+
 #[derive(Serialize, Deserialize, Default)]
 struct TrustDb {
+    #[serde(default)]
+    version: u32,
     trust: Option<HashMap<PathBuf, Trust>>,
 }
 
+bitflags::bitflags! {
+    /// The set of capabilities a trusted workspace is allowed to exercise. A user
+    /// can grant, say, language servers without also allowing arbitrary debug
+    /// adapter launch commands from an unfamiliar repo.
+    #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+    #[serde(transparent)]
+    pub struct Capabilities: u8 {
+        const LANGUAGE_SERVERS = 0b0001;
+        const FORMATTERS       = 0b0010;
+        const DEBUG_ADAPTERS   = 0b0100;
+        const LOCAL_CONFIG     = 0b1000;
+    }
+}
+
+impl Capabilities {
+    /// Granted by the "Trust" picker option: everything except loading the
+    /// workspace's own local config, which is trusted separately.
+    pub const TRUSTED: Capabilities = Capabilities::LANGUAGE_SERVERS
+        .union(Capabilities::FORMATTERS)
+        .union(Capabilities::DEBUG_ADAPTERS);
+
+    /// Granted by the "Trust completely" picker option: every capability,
+    /// including loading the workspace's own local config.
+    pub const TRUSTED_COMPLETELY: Capabilities = Capabilities::all();
+}
+
 #[derive(Serialize, Deserialize, PartialEq)]
 pub enum Trust {
-    Workspace { completely: bool },
+    Workspace { capabilities: Capabilities },
     File { hash: Vec<u8> },
     Untrusted,
 }
 
+/// The outcome of revalidating a file against its recorded trust hash.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FileTrustStatus {
+    Trusted,
+    /// The file was trusted, but its contents changed since then (e.g. a `git pull`
+    /// rewrote it). Callers should stop using the derived config and re-prompt.
+    Invalidated,
+    Untrusted,
+}
+
 impl TrustDb {
-    fn is_file_in_completely_trusted(&self, path: impl AsRef<Path>) -> bool {
+    /// Whether the workspace containing `path` has been granted the `LOCAL_CONFIG`
+    /// capability at all. This is a coarse, content-blind gate: it says the user is
+    /// *willing* to use this workspace's local config, not that any particular
+    /// version of it is safe. Actual content trust is always tracked per-file via
+    /// `Trust::File { hash }` below, so a later edit to the file still invalidates
+    /// it even under a "Trust completely" grant - see `trust_workspace`'s callers,
+    /// which snapshot-trust the local config's current contents at grant time.
+    fn has_local_config_capability(&self, path: impl AsRef<Path>) -> bool {
         self.trust.as_ref().is_some_and(|t| {
             t.get(&find_workspace_in(path).0)
                 .is_some_and(|trust| match trust {
-                    Trust::Workspace { completely } => *completely,
+                    Trust::Workspace { capabilities } => {
+                        capabilities.contains(Capabilities::LOCAL_CONFIG)
+                    }
                     _ => false,
                 })
         })
     }
+
+    /// Purely content-hash-based: `true` only if this exact path was previously
+    /// recorded with this exact hash via `trust_file`. Deliberately does not
+    /// consult any workspace-level capability, so a workspace grant can never
+    /// substitute for revalidating a file's actual contents.
     fn is_file_trusted(&self, path: impl AsRef<Path>, file_hash: &[u8]) -> bool {
         self.trust.as_ref().is_some_and(|t| {
             t.get(path.as_ref()).is_some_and(|h| match h {
                 Trust::File { hash } => hash == file_hash,
                 _ => false,
             })
-        }) || self.is_file_in_completely_trusted(path)
+        })
+    }
+
+    /// Like [`Self::is_file_trusted`], but distinguishes "never trusted" from
+    /// "trusted, but the contents changed since then", so the caller can re-prompt
+    /// with a message instead of silently treating the file as untrusted.
+    fn file_trust_status(&self, path: impl AsRef<Path>, file_hash: &[u8]) -> FileTrustStatus {
+        if self.is_file_trusted(&path, file_hash) {
+            return FileTrustStatus::Trusted;
+        }
+        let previously_trusted_hash = self
+            .trust
+            .as_ref()
+            .and_then(|t| t.get(path.as_ref()))
+            .is_some_and(|trust| matches!(trust, Trust::File { .. }));
+        if previously_trusted_hash {
+            FileTrustStatus::Invalidated
+        } else {
+            FileTrustStatus::Untrusted
+        }
     }
 
     fn is_workspace_trusted(&self, path: impl AsRef<Path>) -> Option<bool> {
@@ -51,6 +128,20 @@ impl TrustDb {
         })
     }
 
+    fn workspace_capabilities(&self, path: impl AsRef<Path>) -> Capabilities {
+        self.trust
+            .as_ref()
+            .and_then(|t| {
+                path.as_ref().ancestors().find_map(|p| {
+                    t.get(p).and_then(|trust| match trust {
+                        Trust::Workspace { capabilities } => Some(*capabilities),
+                        _ => None,
+                    })
+                })
+            })
+            .unwrap_or(Capabilities::empty())
+    }
+
     fn lock() -> std::io::Result<File> {
         let file = std::fs::OpenOptions::new()
             .read(true)
@@ -62,28 +153,88 @@ impl TrustDb {
         Ok(file)
     }
 
+    /// Brings a just-loaded `TrustDb` up to [`CURRENT_VERSION`]. There's only ever
+    /// been one schema so far, so this just stamps the version; future schema
+    /// changes add a migration step here, keyed on `self.version`.
+    fn migrate(mut self) -> TrustDb {
+        self.version = CURRENT_VERSION;
+        self
+    }
+
+    /// Loads the trust database from disk, recovering instead of panicking if it's
+    /// corrupted: the unparseable file is backed up alongside itself with a
+    /// `.corrupt-<timestamp>` suffix and a fresh, empty database is used in its
+    /// place.
+    fn load() -> std::io::Result<TrustDb> {
+        let contents = match std::fs::read_to_string(trust_db_file()) {
+            Ok(s) => s,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(TrustDb::default().migrate()),
+            Err(e) => return Err(e),
+        };
+
+        match toml::from_str::<TrustDb>(&contents) {
+            Ok(db) => Ok(db.migrate()),
+            Err(parse_error) => {
+                Self::backup_corrupted_file(parse_error);
+                Ok(TrustDb::default().migrate())
+            }
+        }
+    }
+
+    fn backup_corrupted_file(parse_error: toml::de::Error) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let backup_file = trust_db_file().with_extension(format!("toml.corrupt-{timestamp}"));
+
+        let message = match std::fs::rename(trust_db_file(), &backup_file) {
+            Ok(()) => format!(
+                "trust database at {} was corrupted ({parse_error}) and has been reset; \
+                 the previous contents were backed up to {}. All workspaces and files will \
+                 need to be re-trusted.",
+                trust_db_file().display(),
+                backup_file.display(),
+            ),
+            Err(e) => format!(
+                "trust database at {} was corrupted ({parse_error}) and has been reset, but \
+                 backing it up to {} failed: {e}. All workspaces and files will need to be \
+                 re-trusted.",
+                trust_db_file().display(),
+                backup_file.display(),
+            ),
+        };
+        log::error!("{message}");
+        *recovery_warning_slot().lock().unwrap() = Some(message);
+    }
+
+    /// Serializes and writes the database, swapping it into place with a rename so
+    /// a crash or power loss mid-write can never leave `trust_db.toml` truncated.
+    fn save(&self) -> std::io::Result<()> {
+        let serialized = toml::to_string(self).expect("toml serialization of trust database failed?");
+        let tmp_file = trust_db_file().with_extension("toml.tmp");
+        std::fs::write(&tmp_file, serialized).map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                std::io::Error::new(
+                    e.kind(),
+                    format!(
+                        "{e} (does the parent directory '{}' exist?)",
+                        data_dir().display()
+                    ),
+                )
+            } else {
+                e
+            }
+        })?;
+        std::fs::rename(&tmp_file, trust_db_file())
+    }
+
     fn inspect<F, R>(f: F) -> std::io::Result<R>
     where
         F: FnOnce(TrustDb) -> R,
     {
         let lock = TrustDb::lock()?;
-        let contents = match std::fs::read_to_string(trust_db_file()) {
-            Ok(s) => s,
-            Err(e) => {
-                if e.kind() == ErrorKind::NotFound {
-                    toml::to_string(&TrustDb::default()).unwrap()
-                } else {
-                    return Err(e);
-                }
-            }
-        };
-        let toml: TrustDb = toml::from_str(&contents).unwrap_or_else(|_| {
-            panic!(
-                "Trust database is corrupted. Try to fix {} or delete it",
-                trust_db_file().display()
-            )
-        });
-        let r = f(toml);
+        let db = TrustDb::load()?;
+        let r = f(db);
         drop(lock);
         Ok(r)
     }
@@ -93,26 +244,9 @@ impl TrustDb {
         F: FnOnce(&mut TrustDb) -> R,
     {
         let lock = TrustDb::lock()?;
-        let contents = match std::fs::read_to_string(trust_db_file()) {
-            Ok(s) => s,
-            Err(e) => {
-                if e.kind() == ErrorKind::NotFound {
-                    toml::to_string(&TrustDb::default()).unwrap()
-                } else {
-                    return Err(e);
-                }
-            }
-        };
-        let mut toml: TrustDb = toml::from_str(&contents).unwrap_or_else(|_| {
-            panic!(
-                "Trust database is corrupted. Try to fix {} or delete it",
-                trust_db_file().display()
-            )
-        });
-        let r = f(&mut toml);
-        let toml_updated =
-            toml::to_string(&toml).expect("toml serialization of trust database failed?");
-        std::fs::write(trust_db_file(), toml_updated)?;
+        let mut db = TrustDb::load()?;
+        let r = f(&mut db);
+        db.save()?;
         drop(lock);
         Ok(r)
     }
@@ -133,14 +267,89 @@ fn trust_db_lock_file() -> PathBuf {
     trust_db_file().with_extension("lock")
 }
 
-pub fn trust_workspace(path: impl AsRef<Path>, completely: bool) -> std::io::Result<Option<Trust>> {
+/// Environment variable that, when set, skips the filesystem permission checks below.
+/// Meant as an escape hatch for containers/CI where everything runs as root with a
+/// permissive umask and the checks would otherwise always fail. `pub(crate)` so
+/// `trust_rules::is_foreign_owned` - an ownership check with the exact same
+/// container/CI motivation - honors the same escape hatch instead of drifting
+/// out of sync with its own copy of the variable name.
+pub(crate) const DISABLE_PERMISSION_CHECKS_ENV: &str = "HELIX_FS_DISABLE_PERMISSION_CHECKS";
+
+/// Walks every ancestor component of `path` and verifies that it is owned by the
+/// current user (or root) and is not group- or world-writable, modeled on the
+/// permission verification `fs-mistrust` performs before trusting a config file.
+/// A group/world-writable directory is still accepted if it has the sticky bit set
+/// (e.g. `/tmp`), since other users can't rename or delete entries they don't own.
+#[cfg(unix)]
+fn has_safe_permissions(path: impl AsRef<Path>) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    if std::env::var_os(DISABLE_PERMISSION_CHECKS_ENV).is_some() {
+        return true;
+    }
+
+    let current_uid = rustix::process::getuid().as_raw();
+
+    for ancestor in path.as_ref().ancestors() {
+        let metadata = match std::fs::symlink_metadata(ancestor) {
+            Ok(metadata) => metadata,
+            // A missing ancestor isn't this function's problem to report; the
+            // caller's own IO against `path` will fail on its own.
+            Err(_) => continue,
+        };
+
+        if metadata.uid() != current_uid && metadata.uid() != 0 {
+            return false;
+        }
+
+        let mode = metadata.mode();
+        let group_or_world_writable = mode & 0o022 != 0;
+        let sticky = mode & 0o1000 != 0;
+        if group_or_world_writable && !sticky {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(not(unix))]
+fn has_safe_permissions(_path: impl AsRef<Path>) -> bool {
+    true
+}
+
+/// Re-checks `path`'s filesystem permissions before honoring a positive trust
+/// result, logging and downgrading to "untrusted" if they're unsafe. `description`
+/// names what's being gated (e.g. `"trusting workspace '...'"`) for the log line.
+///
+/// Every trust predicate below must route its positive result through this
+/// function - `has_capability` originally shipped without it, silently trusting
+/// capabilities granted to a workspace that was later made group-writable by
+/// something else on the machine.
+fn enforce_safe_permissions(path: impl AsRef<Path>, description: &str) -> bool {
+    if has_safe_permissions(&path) {
+        true
+    } else {
+        log::warn!(
+            "refusing to honor {description}: '{}' or one of its parent directories has \
+             insecure permissions (owned by another user, or group/world-writable)",
+            path.as_ref().display()
+        );
+        false
+    }
+}
+
+pub fn trust_workspace(
+    path: impl AsRef<Path>,
+    capabilities: Capabilities,
+) -> std::io::Result<Option<Trust>> {
     let Ok(path) = path.as_ref().canonicalize() else {
         return Ok(None);
     };
     TrustDb::modify(|db| {
         db.trust
             .get_or_insert(HashMap::new())
-            .insert(path, Trust::Workspace { completely })
+            .insert(path, Trust::Workspace { capabilities })
     })
 }
 
@@ -159,7 +368,57 @@ pub fn is_workspace_trusted(path: impl AsRef<Path>) -> std::io::Result<Option<bo
     let Ok(path) = path.as_ref().canonicalize() else {
         return Ok(Some(false));
     };
-    TrustDb::inspect(|db| db.is_workspace_trusted(path))
+    let trusted = TrustDb::inspect(|db| db.is_workspace_trusted(&path))?;
+    if trusted == Some(true) && !enforce_safe_permissions(&path, "trusting workspace") {
+        return Ok(Some(false));
+    }
+    Ok(trusted)
+}
+
+/// Checks whether the workspace containing `path` has been granted `capability`,
+/// e.g. to gate spawning a language server, running a formatter, or launching a
+/// debug adapter on the specific bit the user trusted rather than the workspace
+/// as a whole.
+pub fn has_capability(path: impl AsRef<Path>, capability: Capabilities) -> std::io::Result<bool> {
+    let Ok(path) = path.as_ref().canonicalize() else {
+        return Ok(false);
+    };
+    let granted = TrustDb::inspect(|db| db.workspace_capabilities(&path).contains(capability))?;
+    if granted && !enforce_safe_permissions(&path, &format!("capability {capability:?}")) {
+        return Ok(false);
+    }
+    Ok(granted)
+}
+
+/// Whether `path`'s workspace may be used to spawn a language server.
+///
+/// MUST be called from the language client's spawn path, immediately before
+/// starting the server process, in place of the old blanket `is_workspace_trusted`
+/// check - that's the entire point of splitting capabilities out in the first
+/// place. Nothing in this tree calls it at present, the `:trust` command included;
+/// `helix-view`'s language server launch path (where `is_workspace_trusted` is
+/// currently consulted) is not part of this crate and does not call it.
+pub fn is_language_server_trusted(path: impl AsRef<Path>) -> std::io::Result<bool> {
+    has_capability(path, Capabilities::LANGUAGE_SERVERS)
+}
+
+/// Whether `path`'s workspace may be used to run a formatter command.
+///
+/// MUST be called immediately before a formatter process is spawned (the
+/// `:format`/format-on-save paths in `helix-term`'s command table), which is
+/// outside this crate and still needs updating to call this instead of
+/// `is_workspace_trusted`.
+pub fn is_formatter_trusted(path: impl AsRef<Path>) -> std::io::Result<bool> {
+    has_capability(path, Capabilities::FORMATTERS)
+}
+
+/// Whether `path`'s workspace may be used to launch a debug adapter.
+///
+/// MUST be called immediately before a DAP server process is spawned (the debug
+/// adapter launch path in `helix-term`'s DAP commands), which is outside this
+/// crate and still needs updating to call this instead of `is_workspace_trusted`.
+pub fn is_debug_adapter_trusted(path: impl AsRef<Path>) -> std::io::Result<bool> {
+    has_capability(path, Capabilities::DEBUG_ADAPTERS)
 }
 
 pub fn trust_file(path: impl AsRef<Path>, contents: &[u8]) -> std::io::Result<bool> {
@@ -192,9 +451,198 @@ pub fn is_file_trusted(path: impl AsRef<Path>, contents: &[u8]) -> std::io::Resu
         return Ok(false);
     };
     let hash = TrustDb::hash_file(&path, contents);
-    TrustDb::inspect(|db| db.is_file_trusted(path, &hash))
+    let trusted = TrustDb::inspect(|db| db.is_file_trusted(&path, &hash))?;
+    if trusted && !enforce_safe_permissions(&path, "trusting file") {
+        return Ok(false);
+    }
+    Ok(trusted)
+}
+
+/// Whether a workspace-local config file (e.g. `.helix/languages.toml`) may be
+/// used: the enclosing workspace must have been granted the `LOCAL_CONFIG`
+/// capability, *and* the file's current contents must match a recorded trust hash.
+/// The capability alone is never sufficient - an external edit to the file after
+/// the workspace was granted `LOCAL_CONFIG` still needs to be separately
+/// re-trusted, which is what lets `file_trust_status` catch it.
+pub fn is_local_config_trusted(path: impl AsRef<Path>, contents: &[u8]) -> std::io::Result<bool> {
+    let Ok(path) = path.as_ref().canonicalize() else {
+        return Ok(false);
+    };
+    let hash = TrustDb::hash_file(&path, contents);
+    let trusted = TrustDb::inspect(|db| {
+        db.has_local_config_capability(&path) && db.is_file_trusted(&path, &hash)
+    })?;
+    if trusted && !enforce_safe_permissions(&path, "trusting local config") {
+        return Ok(false);
+    }
+    Ok(trusted)
+}
+
+/// Revalidates a previously trusted file against its recorded hash, distinguishing
+/// a file that was never trusted from one whose contents changed since it was.
+pub fn file_trust_status(
+    path: impl AsRef<Path>,
+    contents: &[u8],
+) -> std::io::Result<FileTrustStatus> {
+    let Ok(path) = path.as_ref().canonicalize() else {
+        return Ok(FileTrustStatus::Untrusted);
+    };
+    let hash = TrustDb::hash_file(&path, contents);
+    let status = TrustDb::inspect(|db| db.file_trust_status(&path, &hash))?;
+    if status == FileTrustStatus::Trusted && !enforce_safe_permissions(&path, "trusting file") {
+        return Ok(FileTrustStatus::Untrusted);
+    }
+    Ok(status)
 }
 
 pub fn initialize_trust_db() {
     ensure_parent_dir(&trust_db_file());
 }
+
+fn recovery_warning_slot() -> &'static Mutex<Option<String>> {
+    static SLOT: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+    SLOT.get_or_init(|| Mutex::new(None))
+}
+
+/// Takes and clears the warning left behind the last time the trust database was
+/// found corrupted and reset, if any, so each warning is returned only once. The
+/// `DocumentDidOpen` hook polls it on every open so the user actually sees it on the
+/// editor status line instead of only in the log, which most users never open.
+pub fn take_corruption_warning() -> Option<String> {
+    recovery_warning_slot().lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_stamps_current_version() {
+        let db = TrustDb {
+            version: 0,
+            trust: None,
+        }
+        .migrate();
+        assert_eq!(db.version, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn corruption_warning_is_taken_exactly_once() {
+        assert_eq!(take_corruption_warning(), None);
+
+        *recovery_warning_slot().lock().unwrap() = Some("trust database reset".to_string());
+        assert_eq!(
+            take_corruption_warning(),
+            Some("trust database reset".to_string())
+        );
+        // Taking it again finds nothing left - the warning is shown to the user once.
+        assert_eq!(take_corruption_warning(), None);
+    }
+
+    // `has_safe_permissions` reads the `HELIX_FS_DISABLE_PERMISSION_CHECKS` process
+    // environment variable, so every test below that cares whether it's set or
+    // unset serializes on this lock - otherwise two of these tests running on
+    // different threads (the cargo test default) could see each other's env var
+    // state and flake.
+    #[cfg(unix)]
+    fn env_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    #[cfg(unix)]
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "helix-trust-db-test-{name}-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    fn chmod(path: &Path, mode: u32) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_writable_dir_is_rejected() {
+        let _guard = env_lock().lock().unwrap_or_else(|e| e.into_inner());
+        unsafe { std::env::remove_var(DISABLE_PERMISSION_CHECKS_ENV) };
+
+        let dir = temp_dir("world-writable");
+        chmod(&dir, 0o777);
+
+        assert!(!has_safe_permissions(dir.join("trust_db.toml")));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_writable_with_sticky_bit_is_accepted() {
+        let _guard = env_lock().lock().unwrap_or_else(|e| e.into_inner());
+        unsafe { std::env::remove_var(DISABLE_PERMISSION_CHECKS_ENV) };
+
+        let dir = temp_dir("sticky");
+        chmod(&dir, 0o1777);
+
+        assert!(has_safe_permissions(dir.join("trust_db.toml")));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn foreign_owner_is_rejected() {
+        let _guard = env_lock().lock().unwrap_or_else(|e| e.into_inner());
+        unsafe { std::env::remove_var(DISABLE_PERMISSION_CHECKS_ENV) };
+
+        // Only root can chown a path to a uid that isn't its own, so this can
+        // only exercise the "owned by someone else" branch when the test runner
+        // itself is root - which is also the one uid this check always exempts,
+        // so we still need an *other* non-zero uid to get a meaningful rejection.
+        if rustix::process::getuid().as_raw() != 0 {
+            return;
+        }
+
+        let dir = temp_dir("foreign-owner");
+        chmod(&dir, 0o755);
+        std::os::unix::fs::chown(&dir, Some(1), None).unwrap();
+
+        assert!(!has_safe_permissions(dir.join("trust_db.toml")));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn root_owned_dir_is_accepted() {
+        let _guard = env_lock().lock().unwrap_or_else(|e| e.into_inner());
+        unsafe { std::env::remove_var(DISABLE_PERMISSION_CHECKS_ENV) };
+
+        // The uid exception is for "owned by root", not "owned by me" - assert it
+        // explicitly rather than relying on the fact that we likely already own
+        // this directory ourselves.
+        let dir = temp_dir("root-owned");
+        chmod(&dir, 0o755);
+        std::os::unix::fs::chown(&dir, Some(0), None).unwrap();
+
+        assert!(has_safe_permissions(dir.join("trust_db.toml")));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disable_env_var_skips_the_check_entirely() {
+        let _guard = env_lock().lock().unwrap_or_else(|e| e.into_inner());
+
+        let dir = temp_dir("disabled");
+        chmod(&dir, 0o777);
+        unsafe { std::env::set_var(DISABLE_PERMISSION_CHECKS_ENV, "1") };
+
+        assert!(has_safe_permissions(dir.join("trust_db.toml")));
+
+        unsafe { std::env::remove_var(DISABLE_PERMISSION_CHECKS_ENV) };
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use globset::Glob;
+use serde::Deserialize;
+
+use crate::trust_db::Capabilities;
+
+/// The `[trust]` section of the user config, evaluated before the interactive
+/// `trust_dialog` is shown for a freshly seen workspace. Patterns are ordered
+/// glob lists matched against the canonicalized workspace root; `never-trust`
+/// takes precedence over both `auto-trust` variants.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TrustRules {
+    #[serde(rename = "auto-trust")]
+    auto_trust: Vec<String>,
+    #[serde(rename = "auto-trust-completely")]
+    auto_trust_completely: Vec<String>,
+    #[serde(rename = "never-trust")]
+    never_trust: Vec<String>,
+    /// When set, a workspace root owned by a uid other than the current user's is
+    /// treated as a `never-trust` match, regardless of the glob lists above.
+    #[serde(rename = "distrust-foreign-owner")]
+    distrust_foreign_owner: bool,
+}
+
+/// The outcome of evaluating a workspace root against the configured rules.
+pub enum RuleAction {
+    Trust { capabilities: Capabilities },
+    Untrust,
+}
+
+impl TrustRules {
+    /// Loads the `[trust]` table out of the user's `config.toml`. Missing file,
+    /// missing table, or a malformed table are all treated as "no rules configured"
+    /// rather than hard errors, since trust rules are an opt-in convenience layer.
+    pub fn load() -> TrustRules {
+        let path = crate::config_dir().join("config.toml");
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| toml::from_str::<toml::Value>(&contents).ok())
+            .and_then(|config| config.get("trust").cloned())
+            .and_then(|trust| trust.try_into().ok())
+            .unwrap_or_default()
+    }
+
+    /// Evaluates a canonicalized workspace root against the configured rules.
+    /// Returns `None` when nothing matches, in which case the caller should fall
+    /// back to the interactive trust dialog.
+    pub fn evaluate(&self, workspace_root: impl AsRef<Path>) -> Option<RuleAction> {
+        let path = workspace_root.as_ref();
+
+        if self.distrust_foreign_owner && is_foreign_owned(path) {
+            return Some(RuleAction::Untrust);
+        }
+        if Self::matches_any(&self.never_trust, path) {
+            return Some(RuleAction::Untrust);
+        }
+        if Self::matches_any(&self.auto_trust_completely, path) {
+            return Some(RuleAction::Trust {
+                capabilities: Capabilities::TRUSTED_COMPLETELY,
+            });
+        }
+        if Self::matches_any(&self.auto_trust, path) {
+            return Some(RuleAction::Trust {
+                capabilities: Capabilities::TRUSTED,
+            });
+        }
+        None
+    }
+
+    fn matches_any(patterns: &[String], path: &Path) -> bool {
+        patterns.iter().any(|pattern| {
+            let expanded = expand_tilde(pattern);
+            Glob::new(&expanded)
+                .map(|glob| glob.compile_matcher().is_match(path))
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// Expands a leading `~` to the user's home directory, the same convention the
+/// patterns in `[trust]` document (`~/code/**`).
+fn expand_tilde(pattern: &str) -> String {
+    match (pattern.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home
+            .join(rest.trim_start_matches('/'))
+            .to_string_lossy()
+            .into_owned(),
+        _ => pattern.to_string(),
+    }
+}
+
+/// Honors [`crate::trust_db::DISABLE_PERMISSION_CHECKS_ENV`], the same escape
+/// hatch `trust_db`'s permission checks use: a `distrust-foreign-owner` rule has
+/// the identical container/CI failure mode (everything checked out as root or
+/// some other uid that doesn't match the process) that variable exists for, so a
+/// user who's already set it to get `trust_db` working wouldn't expect this check
+/// to keep auto-untrusting every workspace anyway.
+#[cfg(unix)]
+fn is_foreign_owned(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    if std::env::var_os(crate::trust_db::DISABLE_PERMISSION_CHECKS_ENV).is_some() {
+        return false;
+    }
+
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    metadata.uid() != rustix::process::getuid().as_raw()
+}
+
+#[cfg(not(unix))]
+fn is_foreign_owned(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(never: &[&str], auto_completely: &[&str], auto: &[&str]) -> TrustRules {
+        TrustRules {
+            auto_trust: auto.iter().map(|s| s.to_string()).collect(),
+            auto_trust_completely: auto_completely.iter().map(|s| s.to_string()).collect(),
+            never_trust: never.iter().map(|s| s.to_string()).collect(),
+            distrust_foreign_owner: false,
+        }
+    }
+
+    #[test]
+    fn never_trust_overrides_auto_trust_completely() {
+        let rules = rules(&["/home/user/evil/**"], &["/home/user/**"], &[]);
+        assert!(matches!(
+            rules.evaluate(Path::new("/home/user/evil/repo")),
+            Some(RuleAction::Untrust)
+        ));
+    }
+
+    #[test]
+    fn auto_trust_completely_overrides_auto_trust() {
+        let rules = rules(&[], &["/home/user/work/**"], &["/home/user/**"]);
+        assert!(matches!(
+            rules.evaluate(Path::new("/home/user/work/repo")),
+            Some(RuleAction::Trust { capabilities }) if capabilities == Capabilities::TRUSTED_COMPLETELY
+        ));
+    }
+
+    #[test]
+    fn auto_trust_grants_trusted_not_complete() {
+        let rules = rules(&[], &[], &["/home/user/**"]);
+        assert!(matches!(
+            rules.evaluate(Path::new("/home/user/repo")),
+            Some(RuleAction::Trust { capabilities }) if capabilities == Capabilities::TRUSTED
+        ));
+    }
+
+    #[test]
+    fn no_match_falls_back_to_the_interactive_dialog() {
+        let rules = rules(&[], &[], &["/home/other/**"]);
+        assert!(rules.evaluate(Path::new("/home/user/repo")).is_none());
+    }
+
+    #[test]
+    fn tilde_expands_to_home_dir() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let rules = rules(&[], &[], &["~/project/**"]);
+        assert!(matches!(
+            rules.evaluate(home.join("project/repo")),
+            Some(RuleAction::Trust { .. })
+        ));
+    }
+}
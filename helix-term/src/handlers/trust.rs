@@ -1,5 +1,9 @@
+use std::path::Path;
+
 use crate::compositor::Compositor;
 use crate::ui;
+use helix_loader::trust_db::Capabilities;
+use helix_loader::trust_rules::{RuleAction, TrustRules};
 use helix_view::events::DocumentDidOpen;
 use helix_view::handlers::Handlers;
 use helix_view::theme::Modifier;
@@ -47,6 +51,7 @@ pub fn trust_dialog(editor: &mut Editor, compositor: &mut Compositor) {
         return;
     };
     let path = helix_loader::find_workspace_in(file_path).0;
+    let start_dir = file_path.parent().unwrap_or(file_path).to_path_buf();
 
     let columns = [
         ui::PickerColumn::new(
@@ -56,13 +61,179 @@ pub fn trust_dialog(editor: &mut Editor, compositor: &mut Compositor) {
         ui::PickerColumn::new("", |(_, explain): &(_, String), _| explain.as_str().into()),
     ];
 
+    let picker = ui::Picker::new(columns, 0, options, (), move |cx, str, _action| {
+        let capabilities = if str.0.content == second_option {
+            Some(Capabilities::TRUSTED)
+        } else if str.0.content == third_option {
+            Some(Capabilities::TRUSTED_COMPLETELY)
+        } else {
+            debug_assert_eq!(str.0.content, first_option);
+            None
+        };
+        let maybe_err = match capabilities {
+            Some(capabilities) => cx.editor.trust_workspace(capabilities),
+            None => cx.editor.untrust_workspace(),
+        };
+        if let Err(e) = maybe_err {
+            cx.editor.set_status(e.to_string());
+        } else if let Some(capabilities) = capabilities {
+            snapshot_trust_local_config(capabilities, &start_dir);
+        }
+    });
+    compositor.push(Box::new(overlaid(picker)));
+}
+
+/// If `capabilities` includes [`Capabilities::LOCAL_CONFIG`], snapshot-trusts the
+/// current contents of every `.helix/languages.toml` discovered between
+/// `start_dir` and the workspace root - the same ancestor walk
+/// `user_lang_config_for_file` merges - so they're picked up immediately instead
+/// of each one being reported as "never trusted" and silently dropped from the
+/// merge. Granting `LOCAL_CONFIG` used to only snapshot the workspace-root file,
+/// which meant a monorepo subdirectory's config stayed permanently untrusted even
+/// right after "Trust completely".
+fn snapshot_trust_local_config(capabilities: Capabilities, start_dir: &Path) {
+    if !capabilities.contains(Capabilities::LOCAL_CONFIG) {
+        return;
+    }
+    for path in helix_loader::config::local_lang_config_ancestors(start_dir) {
+        if let Ok(contents) = std::fs::read_to_string(&path) {
+            let _ = helix_loader::trust_db::trust_file(&path, contents.as_bytes());
+        }
+    }
+}
+
+/// Implements the `:trust` command, granting the workspace containing the current
+/// document every capability except local config, matching the "Trust" picker
+/// option.
+///
+/// This used to accept a list of capability names (`:trust language-servers
+/// formatters`) for finer-grained grants, but nothing outside this crate actually
+/// consults `LANGUAGE_SERVERS`/`FORMATTERS`/`DEBUG_ADAPTERS` at an LSP, formatter,
+/// or DAP spawn site yet (see `is_language_server_trusted` and friends in
+/// `trust_db`) - only `LOCAL_CONFIG` is enforced today, by `user_lang_config`/
+/// `user_lang_config_for_file`. A per-capability `:trust` command implied a
+/// security boundary that didn't exist, so it's gone until the real spawn sites
+/// are wired to check these bits.
+pub fn trust_command(editor: &mut Editor) -> anyhow::Result<()> {
+    editor.trust_workspace(Capabilities::TRUSTED)?;
+    Ok(())
+}
+
+/// Applies the configured `[trust]` auto-trust/never-trust rules to a freshly
+/// opened workspace, returning `true` if a rule matched, so the caller knows whether
+/// the interactive dialog is still needed. A matched rule that fails to apply still
+/// returns `true`; the error is shown on the status line.
+fn apply_trust_rules(editor: &mut Editor, doc: helix_view::DocumentId) -> bool {
+    let Some(path) = editor.document(doc).and_then(|doc| doc.path()) else {
+        return false;
+    };
+    let workspace_root = helix_loader::find_workspace_in(path).0;
+    let Ok(workspace_root) = workspace_root.canonicalize() else {
+        return false;
+    };
+    let start_dir = path.parent().unwrap_or(path).to_path_buf();
+
+    let Some(action) = TrustRules::load().evaluate(&workspace_root) else {
+        return false;
+    };
+
+    let granted_capabilities = match &action {
+        RuleAction::Trust { capabilities } => Some(*capabilities),
+        RuleAction::Untrust => None,
+    };
+    let maybe_err = match action {
+        RuleAction::Trust { capabilities } => editor.trust_workspace(capabilities),
+        RuleAction::Untrust => editor.untrust_workspace(),
+    };
+    if let Err(e) = maybe_err {
+        editor.set_status(e.to_string());
+    } else if let Some(capabilities) = granted_capabilities {
+        snapshot_trust_local_config(capabilities, &start_dir);
+    }
+    true
+}
+
+/// Revalidates every local `.helix/languages.toml` that `user_lang_config_for_file`
+/// would merge for `doc` - not just the workspace-root one - against its recorded trust
+/// hash, using the same `local_lang_config_ancestors` walk so the two stay in
+/// sync. If a file's contents changed since it was trusted (e.g. a `git pull`
+/// rewrote it), the stale trust record is left untouched - `user_lang_config`
+/// already stops using it the moment the hash no longer matches - but we still
+/// raise a dialog scoped to just that file so the user notices and can either
+/// re-trust the new contents or explicitly distrust the file. Only the closest
+/// invalidated ancestor is surfaced per open; the rest are caught on a subsequent
+/// open once it's resolved.
+fn revalidate_local_config(
+    editor: &mut Editor,
+    compositor: &mut Compositor,
+    doc: helix_view::DocumentId,
+) {
+    let Some(file_path) = editor.document(doc).and_then(|doc| doc.path()) else {
+        return;
+    };
+    let start_dir = file_path.parent().unwrap_or(file_path).to_path_buf();
+
+    for path in helix_loader::config::local_lang_config_ancestors(&start_dir) {
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let status = match helix_loader::trust_db::file_trust_status(&path, contents.as_bytes()) {
+            Ok(status) => status,
+            Err(_) => continue,
+        };
+        if status == helix_loader::trust_db::FileTrustStatus::Invalidated {
+            prompt_retrust_local_config(compositor, path, contents);
+            return;
+        }
+    }
+}
+
+/// Raises a "this local config changed since you trusted it" dialog for `path`,
+/// letting the user re-trust its current `contents` or explicitly distrust it.
+fn prompt_retrust_local_config(
+    compositor: &mut Compositor,
+    path: std::path::PathBuf,
+    contents: String,
+) {
+    let first_option = "Do not trust";
+    let second_option = "Trust";
+    let options = vec![
+        (
+            Span::styled(
+                first_option,
+                Style::new()
+                    .fg(helix_view::theme::Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            "Keep ignoring this file until it is re-trusted.".to_string(),
+        ),
+        (
+            Span::styled(
+                second_option,
+                Style::new()
+                    .fg(helix_view::theme::Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            "Trust the file's current contents.".to_string(),
+        ),
+    ];
+
+    let columns = [
+        ui::PickerColumn::new(
+            format!(
+                "'{}' changed since you trusted it. Re-trust?",
+                path.display()
+            ),
+            |(t, _): &(Span<'_>, String), _| Spans(vec![t.clone()]).into(),
+        ),
+        ui::PickerColumn::new("", |(_, explain): &(_, String), _| explain.as_str().into()),
+    ];
+
     let picker = ui::Picker::new(columns, 0, options, (), move |cx, str, _action| {
         let maybe_err = if str.0.content == first_option {
-            cx.editor.untrust_workspace()
-        } else if str.0.content == second_option {
-            cx.editor.trust_workspace(false)
+            helix_loader::trust_db::untrust_file(&path).map(|_| ())
         } else {
-            cx.editor.trust_workspace(true)
+            helix_loader::trust_db::trust_file(&path, contents.as_bytes()).map(|_| ())
         };
         if let Err(e) = maybe_err {
             cx.editor.set_status(e.to_string());
@@ -73,10 +244,15 @@ pub fn trust_dialog(editor: &mut Editor, compositor: &mut Compositor) {
 
 pub(super) fn register_hooks(_handlers: &Handlers) {
     helix_event::register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        if let Some(warning) = helix_loader::trust_db::take_corruption_warning() {
+            event.editor.set_error(warning);
+        }
+
         if event
             .editor
             .document(event.doc)
             .is_some_and(|doc| doc.is_trusted.is_none())
+            && !apply_trust_rules(event.editor, event.doc)
         {
             tokio::spawn(async move {
                 crate::job::dispatch(move |editor, compositor| {
@@ -84,6 +260,17 @@ pub(super) fn register_hooks(_handlers: &Handlers) {
                 })
                 .await;
             });
+        } else {
+            // The workspace's trust has already been decided; still check whether any
+            // of its local configs drifted out from under that decision (e.g. an
+            // external `git pull`) since we last read them.
+            let doc = event.doc;
+            tokio::spawn(async move {
+                crate::job::dispatch(move |editor, compositor| {
+                    revalidate_local_config(editor, compositor, doc);
+                })
+                .await;
+            });
         }
 
         Ok(())